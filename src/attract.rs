@@ -8,6 +8,10 @@ impl Attractors {
     pub fn attract(&self, point: Vec3) -> Vec3 {
         self.0.iter().map(|a| a.attract(point)).sum()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
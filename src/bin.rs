@@ -1,17 +1,20 @@
 use std::fs;
 
 use anyhow::Context;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
-use crate::render::Output;
+use crate::render::{DirectRenderer, Output, PathTracer, Renderer};
 use crate::scene::Scene;
 
-// mod attract;
+mod attract;
+mod bvh;
 mod light;
 mod material;
+mod mesh;
 mod object;
 mod ray;
 mod render;
+mod rng;
 mod scene;
 
 /// Ray-trace a scene simulating photon paths warped by gravity
@@ -24,21 +27,62 @@ struct Cli {
     width: u32,
     #[arg(short, long)]
     height: u32,
+    #[arg(short, long, value_enum, default_value_t = RendererKind::Direct)]
+    renderer: RendererKind,
+    /// Jittered samples per pixel for anti-aliasing.
+    #[arg(long, default_value_t = 1)]
+    samples: u32,
+    /// Accumulation passes per pixel (path tracer only).
+    #[arg(long, default_value_t = 16)]
+    passes: u32,
+    /// Maximum number of bounces (path tracer only).
+    #[arg(long, default_value_t = 8)]
+    max_depth: u32,
+    /// Worker threads; defaults to the available parallelism.
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum RendererKind {
+    Direct,
+    Path,
+}
+
+impl RendererKind {
+    fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            RendererKind::Direct => Box::new(DirectRenderer),
+            RendererKind::Path => Box::new(PathTracer),
+        }
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    let threads = cli.threads.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+
     let output = Output {
         width: cli.width,
         height: cli.height,
         escape: 5.0,
+        dt: 0.1,
+        samples: cli.samples,
+        passes: cli.passes,
+        max_depth: cli.max_depth,
+        threads,
+        path: "output.png".to_string(),
     };
 
     let scene_str = fs::read_to_string(&cli.scene).context("Read scene")?;
     let scene: Scene = serde_yaml::from_str(&scene_str).context("Parse scene")?;
 
-    let pixels = render::render(&scene, &output);
+    let pixels = cli.renderer.renderer().render(&scene, &output);
     output.save_colors(&pixels, "output.png");
     output.save_normals(&pixels, "output.normals.png");
 
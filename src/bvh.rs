@@ -0,0 +1,156 @@
+use glam::Vec3;
+
+use crate::{
+    object::{Inter, Object, ObjectRef},
+    ray::Ray,
+};
+
+/// Axis-aligned bounding box, stored as its minimum and maximum corners.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    fn union(self, other: Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    fn centroid(self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Slab test: does `ray` enter the box before `max_t`?
+    fn hit(&self, ray: &Ray, max_t: f32) -> bool {
+        let inv = ray.dir.recip();
+        let t0 = (self.min - ray.pos) * inv;
+        let t1 = (self.max - ray.pos) * inv;
+        let mut tmin = t0.min(t1);
+        let mut tmax = t0.max(t1);
+
+        // A zero direction component gives `0 * inf = NaN` when the origin is
+        // coplanar with that slab, which would silently corrupt the interval.
+        // The ray is parallel to those slabs, so each contributes an unbounded
+        // span when the origin lies within `[min, max]` and an empty one (which
+        // forces a miss) otherwise.
+        let parallel = ray.dir.cmpeq(Vec3::ZERO);
+        if parallel.any() {
+            let inside = ray.pos.cmpge(self.min) & ray.pos.cmple(self.max);
+            let lo = Vec3::select(inside, Vec3::splat(f32::NEG_INFINITY), Vec3::splat(f32::INFINITY));
+            let hi = Vec3::select(inside, Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
+            tmin = Vec3::select(parallel, lo, tmin);
+            tmax = Vec3::select(parallel, hi, tmax);
+        }
+
+        let enter = tmin.max_element();
+        let exit = tmax.min_element();
+        enter <= exit && exit >= 0.0 && enter < max_t
+    }
+}
+
+/// Bounding-volume hierarchy over the objects of a scene. Leaves hold object
+/// indices so `Inter::object_ref` keeps resolving materials by position.
+#[derive(Clone, Debug)]
+pub enum Bvh {
+    Empty,
+    Leaf {
+        aabb: Aabb,
+        objects: Vec<ObjectRef>,
+    },
+    Node {
+        aabb: Aabb,
+        left: Box<Bvh>,
+        right: Box<Bvh>,
+    },
+}
+
+/// Objects per leaf before we stop splitting.
+const LEAF_SIZE: usize = 2;
+
+impl Bvh {
+    pub fn build(objects: &[Object]) -> Self {
+        let refs = (0..objects.len() as ObjectRef).collect();
+        Self::build_recursive(objects, refs)
+    }
+
+    fn build_recursive(objects: &[Object], mut refs: Vec<ObjectRef>) -> Self {
+        if refs.is_empty() {
+            return Bvh::Empty;
+        }
+
+        let bounds = refs
+            .iter()
+            .map(|&i| objects[i as usize].aabb())
+            .reduce(Aabb::union)
+            .expect("non-empty refs");
+
+        if refs.len() <= LEAF_SIZE {
+            return Bvh::Leaf {
+                aabb: bounds,
+                objects: refs,
+            };
+        }
+
+        // Split at the median centroid along the longest axis of the bounds.
+        let extent = bounds.max - bounds.min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        refs.sort_by(|&l, &r| {
+            let cl = objects[l as usize].aabb().centroid()[axis];
+            let cr = objects[r as usize].aabb().centroid()[axis];
+            cl.partial_cmp(&cr).expect("Floats should be comparable")
+        });
+        let right = refs.split_off(refs.len() / 2);
+
+        Bvh::Node {
+            aabb: bounds,
+            left: Box::new(Self::build_recursive(objects, refs)),
+            right: Box::new(Self::build_recursive(objects, right)),
+        }
+    }
+
+    pub fn intersect(&self, objects: &[Object], ray: &Ray, max_t: f32) -> Option<Inter> {
+        match self {
+            Bvh::Empty => None,
+            Bvh::Leaf { aabb, objects: refs } => {
+                if !aabb.hit(ray, max_t) {
+                    return None;
+                }
+                refs.iter()
+                    .filter_map(|&index| {
+                        objects[index as usize].intersect(ray, max_t).map(|(t, normal)| Inter {
+                            object_ref: index,
+                            t,
+                            point: ray.at(t),
+                            normal,
+                        })
+                    })
+                    .min_by(|l, r| l.t.partial_cmp(&r.t).expect("Floats should be comparable"))
+            }
+            Bvh::Node { aabb, left, right } => {
+                if !aabb.hit(ray, max_t) {
+                    return None;
+                }
+                let l = left.intersect(objects, ray, max_t);
+                let r = right.intersect(objects, ray, max_t);
+                match (l, r) {
+                    (Some(l), Some(r)) => Some(if l.t <= r.t { l } else { r }),
+                    (hit, None) | (None, hit) => hit,
+                }
+            }
+        }
+    }
+}
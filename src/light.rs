@@ -1,8 +1,8 @@
-use std::{cell::RefCell, f32::consts::PI, fs::File, io::BufReader};
+use std::{f32::consts::PI, fs::File, io::BufReader};
 
 use glam::Vec3;
 use image::{DynamicImage, ImageFormat, Rgb32FImage};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use crate::{object::Inter, ray::Ray, render::Color};
 
@@ -39,11 +39,11 @@ impl Light {
         self.diffuse(ray, inter)
     }
 
-    fn diffuse(&self, ray: &Ray, inter: &Inter) -> Color {
+    fn diffuse(&self, _ray: &Ray, inter: &Inter) -> Color {
         match self {
             Light::Ambient { intensity } => *intensity,
             Light::Point { pos, intensity } => {
-                let light_dir = (*pos - ray.at(inter.t)).normalize();
+                let light_dir = (*pos - inter.point).normalize();
                 let dot = inter.normal.dot(light_dir).clamp(0.0, 1.0);
                 *intensity * dot
             }
@@ -59,25 +59,14 @@ impl Light {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// An image-based environment light. The HDRI is loaded eagerly when the scene
+/// is parsed and then only read, so it can be shared across worker threads.
+#[derive(Clone, Debug)]
 pub struct Hdri {
-    pub path: String,
-    #[serde(default, skip)]
-    pub image: RefCell<Option<Rgb32FImage>>,
+    image: Rgb32FImage,
 }
 
 impl Hdri {
-    pub fn load(&self) {
-        let file = File::open(&self.path).expect("Open HDRI");
-        let buf = BufReader::new(file);
-        let image = image::load(buf, ImageFormat::Hdr).expect("Load HDRI");
-        let image = match image {
-            DynamicImage::ImageRgb32F(image) => image,
-            _ => panic!("HDRI format should be Rgb32F"),
-        };
-        *self.image.borrow_mut() = Some(image);
-    }
-
     pub fn sample(&self, dir: Vec3) -> Color {
         // Convert from cartesian coordinates to spherical coordinates
         // θ (theta) is the polar angle from the y-axis (up)
@@ -91,16 +80,37 @@ impl Hdri {
         let u = phi / (2.0 * PI); // Convert [0, 2π] to [0, 1]
         let v = theta / PI; // Convert [0, π] to [0, 1]
 
-        // TODO: Load on startup and remove RefCell
-        if self.image.borrow().is_none() {
-            self.load();
-        }
-        let i = self.image.borrow();
-        let image = i.as_ref().expect("HDRI image not loaded");
-        let (width, height) = image.dimensions();
+        let (width, height) = self.image.dimensions();
         let x = (u * width as f32) as u32;
         let y = ((1.0 - v) * height as f32) as u32;
-        let pixel = image.get_pixel(x, y);
+        let pixel = self.image.get_pixel(x, y);
         Color::new(pixel[0], pixel[1], pixel[2])
     }
 }
+
+impl<'de> Deserialize<'de> for Hdri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw {
+            path: String,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let image = load_hdri(&raw.path).map_err(serde::de::Error::custom)?;
+        Ok(Hdri { image })
+    }
+}
+
+/// Load a Radiance `.hdr` image, rejecting anything that is not `Rgb32F`.
+fn load_hdri(path: &str) -> Result<Rgb32FImage, String> {
+    let file = File::open(path).map_err(|e| format!("Open HDRI {path}: {e}"))?;
+    let buf = BufReader::new(file);
+    let image = image::load(buf, ImageFormat::Hdr).map_err(|e| format!("Load HDRI {path}: {e}"))?;
+    match image {
+        DynamicImage::ImageRgb32F(image) => Ok(image),
+        _ => Err(format!("HDRI {path} is not Rgb32F")),
+    }
+}
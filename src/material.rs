@@ -24,6 +24,7 @@ impl Materials {
 #[serde(tag = "type")]
 pub enum Material {
     Simple { color: Color },
+    Emissive { intensity: Color },
     Normal,
 }
 
@@ -31,7 +32,25 @@ impl Material {
     pub fn color_at(&self, _scene: &Scene, inter: &Inter, lighting: &Lighting) -> Color {
         match self {
             Material::Simple { color } => color * lighting.force,
+            Material::Emissive { intensity } => *intensity,
             Material::Normal => inter.normal.map(|c| 0.5 * (c + 1.0)),
         }
     }
+
+    /// Fraction of incoming light reflected diffusely, used by the path tracer.
+    pub fn albedo(&self) -> Color {
+        match self {
+            Material::Simple { color } => *color,
+            Material::Emissive { .. } => Color::ZERO,
+            Material::Normal => Color::splat(0.5),
+        }
+    }
+
+    /// Light emitted by the surface itself, zero for non-emissive materials.
+    pub fn emitted(&self) -> Color {
+        match self {
+            Material::Emissive { intensity } => *intensity,
+            _ => Color::ZERO,
+        }
+    }
 }
@@ -0,0 +1,203 @@
+use std::fs;
+
+use glam::Vec3;
+use serde::{Deserialize, Deserializer};
+
+use crate::{bvh::Aabb, ray::Ray};
+
+/// Rejection threshold for near-parallel rays / degenerate triangles.
+const EPS: f32 = 1e-8;
+
+/// A triangle-mesh shape loaded from a Wavefront OBJ file. Vertex positions are
+/// required; per-vertex normals are kept when the file provides them.
+#[derive(Clone, Debug)]
+pub struct Mesh {
+    triangles: Vec<Triangle>,
+}
+
+#[derive(Clone, Debug)]
+struct Triangle {
+    positions: [Vec3; 3],
+    normals: Option<[Vec3; 3]>,
+}
+
+impl Triangle {
+    /// Möller–Trumbore intersection, returning `(t, u, v)` on a hit in range.
+    fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, f32, f32)> {
+        let edge1 = self.positions[1] - self.positions[0];
+        let edge2 = self.positions[2] - self.positions[0];
+        let pvec = ray.dir.cross(edge2);
+        let det = edge1.dot(pvec);
+        if det.abs() < EPS {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let tvec = ray.pos - self.positions[0];
+        let u = tvec.dot(pvec) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let qvec = tvec.cross(edge1);
+        let v = ray.dir.dot(qvec) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(qvec) * inv_det;
+        (t > EPS && t < max_t).then_some((t, u, v))
+    }
+
+    /// Interpolated shading normal, or the face normal when none are present.
+    fn normal(&self, u: f32, v: f32) -> Vec3 {
+        match self.normals {
+            Some([n0, n1, n2]) => (n0 * (1.0 - u - v) + n1 * u + n2 * v).normalize(),
+            None => {
+                let edge1 = self.positions[1] - self.positions[0];
+                let edge2 = self.positions[2] - self.positions[0];
+                edge1.cross(edge2).normalize()
+            }
+        }
+    }
+}
+
+impl Mesh {
+    /// Nearest triangle hit together with its barycentric coordinates.
+    fn hit(&self, ray: &Ray, max_t: f32) -> Option<(&Triangle, f32, f32, f32)> {
+        self.triangles
+            .iter()
+            .filter_map(|tri| tri.intersect(ray, max_t).map(|(t, u, v)| (tri, t, u, v)))
+            .min_by(|l, r| l.1.partial_cmp(&r.1).expect("Floats should be comparable"))
+    }
+
+    /// Nearest hit distance together with the interpolated shading normal,
+    /// both recovered from the single Möller–Trumbore pass so the normal costs
+    /// nothing beyond the intersection the BVH already paid for.
+    pub fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, Vec3)> {
+        self.hit(ray, max_t).map(|(tri, t, u, v)| (t, tri.normal(u, v)))
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        let mut min = Vec3::splat(f32::INFINITY);
+        let mut max = Vec3::splat(f32::NEG_INFINITY);
+        for tri in &self.triangles {
+            for p in tri.positions {
+                min = min.min(p);
+                max = max.max(p);
+            }
+        }
+        Aabb::new(min, max)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mesh {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw {
+            path: String,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let triangles = load_obj(&raw.path).map_err(serde::de::Error::custom)?;
+        Ok(Mesh { triangles })
+    }
+}
+
+/// Parse an OBJ file into a flat list of triangles, fan-triangulating faces.
+fn load_obj(path: &str) -> Result<Vec<Triangle>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Read mesh {path}: {e}"))?;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut triangles = Vec::new();
+
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("v") => positions.push(parse_vec3(&mut fields)?),
+            Some("vn") => normals.push(parse_vec3(&mut fields)?),
+            Some("f") => {
+                let verts: Vec<FaceVertex> = fields
+                    .map(|tok| parse_face_vertex(tok, positions.len(), normals.len()))
+                    .collect::<Result<_, _>>()?;
+                // Fan-triangulate arbitrary polygons.
+                for i in 1..verts.len().saturating_sub(1) {
+                    triangles.push(build_triangle(
+                        &positions,
+                        &normals,
+                        [verts[0], verts[i], verts[i + 1]],
+                    )?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(triangles)
+}
+
+#[derive(Clone, Copy)]
+struct FaceVertex {
+    position: usize,
+    normal: Option<usize>,
+}
+
+fn build_triangle(
+    positions: &[Vec3],
+    normals: &[Vec3],
+    verts: [FaceVertex; 3],
+) -> Result<Triangle, String> {
+    let positions = [
+        *positions.get(verts[0].position).ok_or("Face vertex out of range")?,
+        *positions.get(verts[1].position).ok_or("Face vertex out of range")?,
+        *positions.get(verts[2].position).ok_or("Face vertex out of range")?,
+    ];
+    let normals = verts
+        .iter()
+        .map(|v| v.normal.and_then(|i| normals.get(i)).copied())
+        .collect::<Option<Vec<_>>>()
+        .map(|ns| [ns[0], ns[1], ns[2]]);
+    Ok(Triangle { positions, normals })
+}
+
+fn parse_vec3<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<Vec3, String> {
+    let mut next = || {
+        fields
+            .next()
+            .ok_or("Missing coordinate")?
+            .parse::<f32>()
+            .map_err(|e| format!("Bad coordinate: {e}"))
+    };
+    Ok(Vec3::new(next()?, next()?, next()?))
+}
+
+/// Parse a `v`, `v/vt` or `v/vt/vn` face token, resolving 1-based and negative
+/// indices into absolute zero-based slots.
+fn parse_face_vertex(token: &str, vertices: usize, normals: usize) -> Result<FaceVertex, String> {
+    let mut parts = token.split('/');
+    let position = resolve_index(parts.next().unwrap_or(""), vertices)?
+        .ok_or("Face vertex missing position")?;
+    let _texcoord = parts.next();
+    let normal = match parts.next() {
+        Some(s) => resolve_index(s, normals)?,
+        None => None,
+    };
+    Ok(FaceVertex { position, normal })
+}
+
+fn resolve_index(s: &str, count: usize) -> Result<Option<usize>, String> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let i = s.parse::<i32>().map_err(|e| format!("Bad index: {e}"))?;
+    let resolved = if i < 0 {
+        count as i32 + i
+    } else {
+        i - 1
+    };
+    Ok(Some(resolved.max(0) as usize))
+}
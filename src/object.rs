@@ -1,9 +1,11 @@
 use glam::Vec3;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use crate::{
+    bvh::{Aabb, Bvh},
     light::Lighting,
-    material::{MaterialRef, DEFAULT_MATERIAL},
+    mesh::Mesh,
+    material::{Material, MaterialRef, DEFAULT_MATERIAL},
     ray::Ray,
     render::Color,
     scene::Scene,
@@ -19,34 +21,24 @@ pub struct Inter {
 
 pub type ObjectRef = u32;
 
-#[derive(Clone, Debug, Deserialize)]
-pub struct Objects(Vec<Object>);
+#[derive(Clone, Debug)]
+pub struct Objects {
+    objects: Vec<Object>,
+    bvh: Bvh,
+}
 
 impl Objects {
     pub fn new(objects: Vec<Object>) -> Self {
-        Self(objects)
+        let bvh = Bvh::build(&objects);
+        Self { objects, bvh }
     }
 
     pub fn get(&self, object_ref: ObjectRef) -> &Object {
-        &self.0[object_ref as usize]
+        &self.objects[object_ref as usize]
     }
 
     pub fn intersect(&self, ray: &Ray, max_t: f32) -> Option<Inter> {
-        self.0
-            .iter()
-            .enumerate()
-            .filter_map(|(index, object)| {
-                object.intersect_in(ray, max_t).map(|t| {
-                    let point = ray.at(t);
-                    Inter {
-                        object_ref: index as ObjectRef,
-                        t,
-                        point,
-                        normal: object.normal_at(point),
-                    }
-                })
-            })
-            .min_by(|l, r| l.t.partial_cmp(&r.t).expect("Floats should be comparable"))
+        self.bvh.intersect(&self.objects, ray, max_t)
     }
 
     pub fn color_at(&self, scene: &Scene, inter: &Inter, lighting: &Lighting) -> Color {
@@ -55,6 +47,26 @@ impl Objects {
             .get(inter.object_ref)
             .color_at(scene, inter, lighting)
     }
+
+    /// Resolve the surface material of an object, falling back to the default.
+    pub fn material_of<'a>(&self, scene: &'a Scene, object_ref: ObjectRef) -> &'a Material {
+        self.get(object_ref)
+            .material_ref
+            .map(|material_ref| scene.materials.get(material_ref))
+            .unwrap_or(&DEFAULT_MATERIAL)
+    }
+}
+
+impl<'de> Deserialize<'de> for Objects {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Scenes list objects as a plain sequence; build the BVH once the whole
+        // list is known so traversal is ready before the first ray is cast.
+        let objects = Vec::<Object>::deserialize(deserializer)?;
+        Ok(Objects::new(objects))
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -72,12 +84,12 @@ impl Object {
         }
     }
 
-    pub fn intersect_in(&self, ray: &Ray, max_t: f32) -> Option<f32> {
-        self.shape.intersect_in(ray, max_t)
+    pub fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, Vec3)> {
+        self.shape.intersect(ray, max_t)
     }
 
-    pub fn normal_at(&self, point: Vec3) -> Vec3 {
-        self.shape.normal_at(point)
+    pub fn aabb(&self) -> Aabb {
+        self.shape.aabb()
     }
 
     pub fn color_at(&self, scene: &Scene, inter: &Inter, lighting: &Lighting) -> Color {
@@ -100,26 +112,32 @@ impl Object {
 pub enum Shape {
     Sphere(Sphere),
     MetaBalls(MetaBalls),
+    Mesh(Mesh),
 }
 
 impl Shape {
-    pub fn intersect_in(&self, ray: &Ray, max_t: f32) -> Option<f32> {
+    /// Nearest hit within `max_t`, returning the distance and the surface
+    /// normal there. Computing both together lets the normal reuse the work of
+    /// the intersection instead of re-tracing the shape.
+    pub fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, Vec3)> {
         match self {
-            Shape::Sphere(sphere) => sphere.intersect_in(ray, max_t),
-            Shape::MetaBalls(meta_balls) => meta_balls.intersect_in(ray, max_t),
+            Shape::Sphere(sphere) => sphere.intersect(ray, max_t),
+            Shape::MetaBalls(meta_balls) => meta_balls.intersect(ray, max_t),
+            Shape::Mesh(mesh) => mesh.intersect(ray, max_t),
         }
     }
 
-    pub fn normal_at(&self, point: Vec3) -> Vec3 {
+    pub fn aabb(&self) -> Aabb {
         match self {
-            Shape::Sphere(sphere) => sphere.normal_at(point),
-            Shape::MetaBalls(meta_balls) => meta_balls.normal_at(point),
+            Shape::Sphere(sphere) => sphere.aabb(),
+            Shape::MetaBalls(meta_balls) => meta_balls.aabb(),
+            Shape::Mesh(mesh) => mesh.aabb(),
         }
     }
 
     pub fn color_at(&self, scene: &Scene, inter: &Inter, lighting: &Lighting) -> Option<Color> {
         match self {
-            Shape::Sphere(_) => None,
+            Shape::Sphere(_) | Shape::Mesh(_) => None,
             Shape::MetaBalls(meta_balls) => meta_balls.color_at(scene, inter, lighting),
         }
     }
@@ -129,11 +147,19 @@ impl Shape {
 pub struct Sphere {
     pub center: Vec3,
     pub radius: f32,
+    /// Per-unit-time displacement; `center` is evaluated at `ray.time`.
+    #[serde(default)]
+    pub velocity: Vec3,
 }
 
 impl Sphere {
-    fn intersect_in(&self, ray: &Ray, max_t: f32) -> Option<f32> {
-        let oc = ray.pos - self.center;
+    /// Center of the sphere at a given shutter instant.
+    fn center_at(&self, time: f32) -> Vec3 {
+        self.center + self.velocity * time
+    }
+
+    fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, Vec3)> {
+        let oc = ray.pos - self.center_at(ray.time);
         let a = ray.dir.length_squared();
         let b = 2.0 * oc.dot(ray.dir);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -143,17 +169,24 @@ impl Sphere {
             return None;
         }
 
-        let t = (-b - discriminant.sqrt()) / (2.0 * a);
-        if t > 0.0 {
-            (t < max_t).then_some(t)
-        } else {
-            let t = (-b + discriminant.sqrt()) / (2.0 * a);
-            (t < max_t).then_some(t)
-        }
+        let sqrt_d = discriminant.sqrt();
+        let t = (-b - sqrt_d) / (2.0 * a);
+        let t = if t > 0.0 { t } else { (-b + sqrt_d) / (2.0 * a) };
+        (t < max_t).then(|| {
+            let point = ray.at(t);
+            (t, (point - self.center_at(ray.time)).normalize())
+        })
     }
 
-    fn normal_at(&self, point: Vec3) -> Vec3 {
-        (point - self.center).normalize()
+    fn aabb(&self) -> Aabb {
+        // Bound the swept sphere across the whole shutter interval.
+        let r = Vec3::splat(self.radius);
+        let start = self.center - r;
+        let end = self.center + self.velocity - r;
+        Aabb::new(
+            start.min(end),
+            start.max(end) + 2.0 * r,
+        )
     }
 }
 
@@ -166,14 +199,14 @@ pub struct MetaBalls {
 }
 
 impl MetaBalls {
-    fn intersect_in(&self, ray: &Ray, max_t: f32) -> Option<f32> {
+    fn intersect(&self, ray: &Ray, max_t: f32) -> Option<(f32, Vec3)> {
         let power = self.balls.iter().map(|ball| ball.power).sum::<f32>();
         let mut t = 0.0;
         while t < max_t {
             let point = ray.pos + t * ray.dir;
-            let force = self.force_at(point);
+            let force = self.force_at(point, ray.time);
             if (force - self.threshold).abs() < 0.001 {
-                return Some(t);
+                return Some((t, self.normal_at(point, ray)));
             }
 
             let point_dist = power / force;
@@ -183,17 +216,40 @@ impl MetaBalls {
         None
     }
 
-    fn normal_at(&self, point: Vec3) -> Vec3 {
+    fn normal_at(&self, point: Vec3, ray: &Ray) -> Vec3 {
         -self
             .balls
             .iter()
-            .map(|ball| ball.force_at(point))
+            .map(|ball| ball.force_at(point, ray.time))
             .sum::<Vec3>()
             .normalize()
     }
 
-    fn force_at(&self, point: Vec3) -> f32 {
-        self.balls.iter().map(|ball| ball.strength_at(point)).sum()
+    fn force_at(&self, point: Vec3, time: f32) -> f32 {
+        self.balls
+            .iter()
+            .map(|ball| ball.strength_at(point, time))
+            .sum()
+    }
+
+    fn aabb(&self) -> Aabb {
+        // Where fields overlap the summed strength can exceed `threshold` at a
+        // point outside every single ball's own influence sphere (the "bridge"),
+        // so bounding by `ball.power / threshold` would clip that geometry. On
+        // the isosurface `threshold = Σ pᵢ/dᵢ ≤ (Σ pᵢ) / min dᵢ`, hence the
+        // nearest center is within `(Σ pᵢ) / threshold`; using that total-power
+        // radius around each center conservatively contains the summed surface.
+        let total_power = self.balls.iter().map(|ball| ball.power).sum::<f32>();
+        self.balls
+            .iter()
+            .map(|ball| {
+                let r = Vec3::splat(total_power / self.threshold);
+                let start = ball.center - r;
+                let end = ball.center + ball.velocity - r;
+                Aabb::new(start.min(end), start.max(end) + 2.0 * r)
+            })
+            .reduce(|l, r| Aabb::new(l.min.min(r.min), l.max.max(r.max)))
+            .expect("MetaBalls should have at least one ball")
     }
 
     fn color_at(&self, scene: &Scene, inter: &Inter, lighting: &Lighting) -> Option<Color> {
@@ -205,7 +261,7 @@ impl MetaBalls {
                 (l, r) => l.or(r),
             })
             .flatten()
-            .map(|color| color / self.force_at(inter.point))
+            .map(|color| color / self.force_at(inter.point, 0.0))
     }
 }
 
@@ -214,16 +270,25 @@ pub struct MetaBall {
     pub material_ref: Option<MaterialRef>,
     pub center: Vec3,
     pub power: f32,
+    /// Per-unit-time displacement; `center` is evaluated at `ray.time` for
+    /// intersection and normals, while `color_at` blends colors using the
+    /// centers at time `0`.
+    #[serde(default)]
+    pub velocity: Vec3,
 }
 
 impl MetaBall {
-    fn strength_at(&self, point: Vec3) -> f32 {
-        let diff = point - self.center;
+    fn center_at(&self, time: f32) -> Vec3 {
+        self.center + self.velocity * time
+    }
+
+    fn strength_at(&self, point: Vec3, time: f32) -> f32 {
+        let diff = point - self.center_at(time);
         self.power / diff.length()
     }
 
-    fn force_at(&self, point: Vec3) -> Vec3 {
-        let diff = self.center - point;
+    fn force_at(&self, point: Vec3, time: f32) -> Vec3 {
+        let diff = self.center_at(time) - point;
         let strength = self.power / diff.length();
         strength * diff.normalize()
     }
@@ -234,7 +299,7 @@ impl MetaBall {
                 .materials
                 .get(material_ref)
                 .color_at(scene, inter, lighting)
-                * self.strength_at(inter.point)
+                * self.strength_at(inter.point, 0.0)
         })
     }
 }
@@ -1,17 +1,27 @@
+use std::f32::consts::PI;
+
 use glam::Vec3;
 use serde::Deserialize;
 
 use crate::render::Output;
+use crate::rng::Rng;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Ray {
     pub pos: Vec3,
     pub dir: Vec3,
+    /// Sampling instant in `[0, 1)` within the shutter interval, used for
+    /// motion blur. Defaults to `0`, so still scenes are unaffected.
+    pub time: f32,
 }
 
 impl Ray {
     pub fn new(pos: Vec3, dir: Vec3) -> Self {
-        Self { pos, dir }
+        Self {
+            pos,
+            dir,
+            time: 0.0,
+        }
     }
 
     pub fn at(&self, t: f32) -> Vec3 {
@@ -25,15 +35,29 @@ pub struct Camera {
     pub dir: Vec3,
     pub up: Vec3,
     pub fov: f32,
+    /// Lens diameter; `0` keeps an ideal pinhole with no defocus blur.
+    #[serde(default)]
+    pub aperture: f32,
+    /// Distance to the plane in perfect focus when `aperture > 0`.
+    #[serde(default)]
+    pub focus_dist: f32,
 }
 
 impl Camera {
     /// Sets the camera to look at a specific target position
     pub fn look_at(pos: Vec3, target: Vec3, up: Vec3, fov: f32) -> Self {
-        let dir = (target - pos).normalize();
+        let offset = target - pos;
+        let dir = offset.normalize();
         let right = dir.cross(up).normalize();
         let up = right.cross(dir).normalize();
-        Self { pos, dir, up, fov }
+        Self {
+            pos,
+            dir,
+            up,
+            fov,
+            aperture: 0.0,
+            focus_dist: offset.length(),
+        }
     }
 
     fn right(&self) -> Vec3 {
@@ -43,6 +67,16 @@ impl Camera {
     pub fn project(&self, output: &Output) -> ProjectIter {
         ProjectIter::new(self.clone(), output.clone())
     }
+
+    /// Project only the horizontal band of rows `[y0, y1)` for accumulation
+    /// pass `pass`. Because sampling is reseeded per pixel from its coordinates
+    /// and the pass index, a band yields exactly the rays the full-frame
+    /// iterator would for those rows, letting worker threads own disjoint bands
+    /// without affecting the result, while successive passes draw fresh jitter,
+    /// lens and shutter samples.
+    pub fn project_band(&self, output: &Output, y0: u32, y1: u32, pass: u32) -> ProjectIter {
+        ProjectIter::band(self.clone(), output.clone(), y0, y1, pass)
+    }
 }
 
 pub struct ProjectIter {
@@ -52,20 +86,46 @@ pub struct ProjectIter {
     screen_right: Vec3,
     x: u32,
     y: u32,
+    /// Sample index within the current pixel.
+    s: u32,
+    /// One row past the last row this iterator covers.
+    y_end: u32,
+    /// Accumulation pass this iterator belongs to; folded into the per-pixel
+    /// seed so each pass draws an independent set of camera samples.
+    pass: u32,
+    samples: u32,
+    /// Side length of the stratification grid (`samples.sqrt()`).
+    strata: u32,
+    rng: Rng,
 }
 
 impl ProjectIter {
     pub fn new(cam: Camera, out: Output) -> Self {
+        let height = out.height;
+        Self::band(cam, out, 0, height, 0)
+    }
+
+    /// Build an iterator restricted to rows `[y0, y1)` for accumulation pass `pass`.
+    pub fn band(cam: Camera, out: Output, y0: u32, y1: u32, pass: u32) -> Self {
         let screen_down = -cam.up * cam.fov.tan();
         let screen_right = cam.right() * cam.fov.tan() * out.aspect_ratio();
+        let samples = out.samples.max(1);
+        let strata = (samples as f32).sqrt().round().max(1.0) as u32;
+        let y_end = y1.min(out.height);
 
         Self {
             cam,
             out,
             x: 0,
-            y: 0,
+            y: y0,
+            s: 0,
+            y_end,
+            pass,
+            samples,
+            strata,
             screen_down,
             screen_right,
+            rng: Rng::new(0),
         }
     }
 }
@@ -74,35 +134,98 @@ impl Iterator for ProjectIter {
     type Item = PixelRay;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.y >= self.out.height {
+        if self.y >= self.y_end {
             return None;
         }
 
-        let x = (self.x as f32 + 0.5) / self.out.width as f32;
-        let y = (self.y as f32 + 0.5) / self.out.height as f32;
+        // Reseed per pixel and per pass so sampling is independent of iteration
+        // order yet advances to a fresh stream on each accumulation pass.
+        if self.s == 0 {
+            self.rng = Rng::new(pixel_seed(self.x, self.y, self.pass));
+        }
+
+        // A single sample keeps the pixel center so existing scenes render
+        // identically; more samples are jittered within stratified sub-cells.
+        let (jx, jy) = if self.samples == 1 {
+            (0.5, 0.5)
+        } else {
+            let sx = self.s % self.strata;
+            let sy = (self.s / self.strata) % self.strata;
+            (
+                (sx as f32 + self.rng.next_f32()) / self.strata as f32,
+                (sy as f32 + self.rng.next_f32()) / self.strata as f32,
+            )
+        };
+
+        let x = (self.x as f32 + jx) / self.out.width as f32;
+        let y = (self.y as f32 + jy) / self.out.height as f32;
 
         let dir = self.cam.dir + self.screen_right * (x - 0.5) + self.screen_down * (y - 0.5);
         let dir = dir.normalize();
 
+        // Thin-lens defocus: with an open aperture the origin is jittered over a
+        // disk and aimed at the focal point, so only the focus plane stays sharp.
+        // A zero aperture leaves the pinhole path untouched.
+        let mut ray = if self.cam.aperture > 0.0 {
+            let focus = self.cam.pos + dir * self.cam.focus_dist;
+            // Stratify the lens disk over the same grid as the pixel jitter,
+            // with the axes swapped to decorrelate the two, so defocus blur
+            // converges with `samples` and not by passes alone.
+            let (lu, lv) = if self.samples == 1 {
+                (self.rng.next_f32(), self.rng.next_f32())
+            } else {
+                let sx = self.s % self.strata;
+                let sy = (self.s / self.strata) % self.strata;
+                (
+                    (sy as f32 + self.rng.next_f32()) / self.strata as f32,
+                    (sx as f32 + self.rng.next_f32()) / self.strata as f32,
+                )
+            };
+            let r = self.cam.aperture * 0.5 * lu.sqrt();
+            let theta = 2.0 * PI * lv;
+            let offset =
+                self.cam.right() * (r * theta.cos()) + self.cam.up * (r * theta.sin());
+            let origin = self.cam.pos + offset;
+            Ray::new(origin, (focus - origin).normalize())
+        } else {
+            Ray::new(self.cam.pos, dir)
+        };
+        // Spread samples across the shutter interval so moving geometry blurs.
+        ray.time = self.rng.next_f32();
+
         let ray_pixel = PixelRay {
             x: self.x,
             y: self.y,
-            ray: Ray::new(self.cam.pos, dir),
+            s: self.s,
+            ray,
         };
 
-        self.x += 1;
-        if self.x >= self.out.width {
-            self.x = 0;
-            self.y += 1;
+        self.s += 1;
+        if self.s >= self.samples {
+            self.s = 0;
+            self.x += 1;
+            if self.x >= self.out.width {
+                self.x = 0;
+                self.y += 1;
+            }
         }
 
         Some(ray_pixel)
     }
 }
 
+fn pixel_seed(x: u32, y: u32, pass: u32) -> u64 {
+    (x as u64)
+        .wrapping_mul(9781)
+        .wrapping_add((y as u64).wrapping_mul(6151))
+        .wrapping_add((pass as u64).wrapping_mul(15485863))
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct PixelRay {
     pub x: u32,
     pub y: u32,
+    /// Sample index within the pixel, in `[0, samples)`.
+    pub s: u32,
     pub ray: Ray,
 }
@@ -1,29 +1,295 @@
+use std::f32::consts::PI;
+
 use glam::Vec3;
 use image::ImageBuffer;
 use serde::Deserialize;
 
-use crate::{object::Inter, scene::Scene};
-
-pub fn render(scene: &Scene, out: &Output) -> Vec<Pixel> {
-    scene
-        .camera
-        .project(out)
-        .map(|px_ray| {
-            let Some(inter) = scene.objects.intersect(&px_ray.ray, out.escape) else {
-                return Pixel::NoInter;
-            };
-            let lighting = scene.lights.lighting(&px_ray.ray, &inter);
-            let color = scene.objects.color_at(scene, &inter, &lighting);
-            Pixel::Inter { inter, color }
-        })
-        .collect()
+use crate::{object::Inter, ray::Ray, rng::Rng, scene::Scene};
+
+/// A strategy for turning a scene into a frame of pixels.
+pub trait Renderer {
+    fn render(&self, scene: &Scene, out: &Output) -> Vec<Pixel>;
+}
+
+/// Single-bounce direct-lighting renderer (the original Whitted-style path):
+/// one primary hit plus one lighting evaluation per pixel.
+pub struct DirectRenderer;
+
+impl Renderer for DirectRenderer {
+    fn render(&self, scene: &Scene, out: &Output) -> Vec<Pixel> {
+        let samples = out.samples.max(1) as f32;
+        let width = out.width;
+        let mut frame = Frame::new(out);
+
+        std::thread::scope(|scope| {
+            for (y0, band) in frame.bands(out.threads.max(1)) {
+                let rows = band.len() as u32 / width;
+                scope.spawn(move || {
+                    for px in scene.camera.project_band(out, y0, y0 + rows, 0) {
+                        let sample = match scene.trace(&px.ray, out) {
+                            Some(inter) => {
+                                let lighting = scene.lights.lighting(&px.ray, &inter);
+                                let color = scene.objects.color_at(scene, &inter, &lighting);
+                                Sample::Hit { inter, color }
+                            }
+                            // A miss contributes background (black) to the average.
+                            None => Sample::Miss,
+                        };
+                        let i = (px.x + (px.y - y0) * width) as usize;
+                        band[i].add(sample);
+                    }
+                });
+            }
+        });
+
+        frame.to_pixels(samples)
+    }
+}
+
+/// Monte-Carlo path tracer with cosine-weighted diffuse bounces and
+/// `Emissive` surfaces as light sources. Colors are refined by averaging
+/// `Output::passes` passes of `Output::samples` independent samples per pixel
+/// as a running mean.
+pub struct PathTracer;
+
+impl PathTracer {
+    /// Estimate the radiance arriving along `ray` by recursively bouncing.
+    fn radiance(&self, scene: &Scene, ray: &Ray, out: &Output, depth: u32, rng: &mut Rng) -> Color {
+        if depth > out.max_depth {
+            return BLACK;
+        }
+        let time = ray.time;
+        let Some(inter) = scene.trace(ray, out) else {
+            return BLACK;
+        };
+        self.shade(scene, &inter, out, depth, time, rng)
+    }
+
+    /// Outgoing radiance at a known surface hit. `time` is carried onto the
+    /// bounce ray so every segment of a path shares one shutter instant.
+    fn shade(
+        &self,
+        scene: &Scene,
+        inter: &Inter,
+        out: &Output,
+        depth: u32,
+        time: f32,
+        rng: &mut Rng,
+    ) -> Color {
+        let material = scene.objects.material_of(scene, inter.object_ref);
+        let emitted = material.emitted();
+        let albedo = material.albedo();
+
+        // Cosine-weighted hemisphere sampling makes the lambertian BRDF and the
+        // sampling PDF cancel, leaving the albedo as the throughput. Because no
+        // division by the PDF survives, zero-weight samples cannot produce NaNs.
+        let dir = cosine_sample_hemisphere(inter.normal, rng);
+        let mut bounce = Ray::new(inter.point + inter.normal * NUDGE, dir);
+        bounce.time = time;
+        emitted + albedo * self.radiance(scene, &bounce, out, depth + 1, rng)
+    }
+}
+
+impl Renderer for PathTracer {
+    fn render(&self, scene: &Scene, out: &Output) -> Vec<Pixel> {
+        let passes = out.passes.max(1);
+        let samples = out.samples.max(1);
+        let width = out.width;
+        let mut frame = Frame::new(out);
+
+        // Passes run sequentially; within each pass bands render in parallel.
+        // After every pass the running average is flushed so the image can be
+        // watched converging and the render stopped early.
+        for pass in 0..passes {
+            std::thread::scope(|scope| {
+                for (y0, band) in frame.bands(out.threads.max(1)) {
+                    let rows = band.len() as u32 / width;
+                    scope.spawn(move || {
+                        for px in scene.camera.project_band(out, y0, y0 + rows, pass) {
+                            // Seed from coordinates and the global sample index so
+                            // each stream is distinct and independent of threading.
+                            let s = pass * samples + px.s;
+                            let mut rng = Rng::new(pixel_seed(px.x, px.y, s));
+                            let sample = match scene.trace(&px.ray, out) {
+                                Some(inter) => {
+                                    let color =
+                                        self.shade(scene, &inter, out, 0, px.ray.time, &mut rng);
+                                    Sample::Hit { inter, color }
+                                }
+                                None => Sample::Miss,
+                            };
+                            let i = (px.x + (px.y - y0) * width) as usize;
+                            band[i].add(sample);
+                        }
+                    });
+                }
+            });
+
+            let divisor = ((pass + 1) * samples) as f32;
+            out.save_colors(&frame.to_pixels(divisor), &out.path);
+        }
+
+        frame.to_pixels((passes * samples) as f32)
+    }
+}
+
+/// The result of tracing one camera sample.
+enum Sample {
+    Hit { inter: Inter, color: Color },
+    Miss,
 }
 
+/// Running totals for a single pixel, averaged across samples.
+///
+/// Keeping the per-pixel state in one struct lets the accumulator be split into
+/// disjoint row bands (`&mut [Accum]`) that worker threads own without locking.
+#[derive(Clone)]
+struct Accum {
+    color: Color,
+    normal: Vec3,
+    hits: u32,
+    rep: Option<Inter>,
+}
+
+impl Accum {
+    fn add(&mut self, sample: Sample) {
+        match sample {
+            Sample::Hit { inter, color } => {
+                self.color += color;
+                self.normal += inter.normal;
+                self.hits += 1;
+                if self.rep.is_none() {
+                    self.rep = Some(inter);
+                }
+            }
+            // A miss leaves the background (black) contribution implicit.
+            Sample::Miss => {}
+        }
+    }
+}
+
+/// Per-pixel accumulator that averages color and normals across samples.
+struct Frame {
+    width: u32,
+    pixels: Vec<Accum>,
+}
+
+impl Frame {
+    fn new(out: &Output) -> Self {
+        let n = (out.width * out.height) as usize;
+        Self {
+            width: out.width,
+            pixels: vec![
+                Accum {
+                    color: BLACK,
+                    normal: Vec3::ZERO,
+                    hits: 0,
+                    rep: None,
+                };
+                n
+            ],
+        }
+    }
+
+    /// Split the accumulator into up to `bands` horizontal row bands, returning
+    /// for each a disjoint mutable slice and the index of its first row. Worker
+    /// threads render one band each, writing into non-overlapping memory.
+    fn bands(&mut self, bands: usize) -> Vec<(u32, &mut [Accum])> {
+        let height = (self.pixels.len() / self.width as usize) as u32;
+        let bands = bands.max(1).min(height.max(1) as usize) as u32;
+        let rows_per_band = height.div_ceil(bands);
+
+        let mut out = Vec::new();
+        let mut rest = self.pixels.as_mut_slice();
+        let mut y0 = 0;
+        while y0 < height {
+            let rows = rows_per_band.min(height - y0);
+            let (band, tail) = rest.split_at_mut((rows * self.width) as usize);
+            out.push((y0, band));
+            rest = tail;
+            y0 += rows;
+        }
+        out
+    }
+
+    /// Resolve pixels, dividing accumulated color by the sample count and
+    /// renormalizing the averaged normal for the normals pass.
+    fn to_pixels(&self, divisor: f32) -> Vec<Pixel> {
+        self.pixels
+            .iter()
+            .map(|acc| match &acc.rep {
+                Some(inter) if acc.hits > 0 => {
+                    let mut inter = inter.clone();
+                    inter.normal = (acc.normal / acc.hits as f32).normalize();
+                    Pixel::Inter {
+                        inter,
+                        color: acc.color / divisor,
+                    }
+                }
+                _ => Pixel::NoInter,
+            })
+            .collect()
+    }
+}
+
+/// Sample a cosine-weighted direction in the hemisphere about `normal`.
+fn cosine_sample_hemisphere(normal: Vec3, rng: &mut Rng) -> Vec3 {
+    // A zero-length or non-finite normal (e.g. a degenerate mesh face) would
+    // turn the basis and final `normalize()` into NaNs; fall back to an
+    // arbitrary axis so a bad hit contributes a finite direction rather than
+    // poisoning the pixel average.
+    let normal = normal.normalize_or_zero();
+    let normal = if normal == Vec3::ZERO { Vec3::Y } else { normal };
+
+    let r1 = rng.next_f32();
+    let r2 = rng.next_f32();
+    let phi = 2.0 * PI * r1;
+    let r = r2.sqrt();
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    (tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + normal * (1.0 - r2).sqrt())
+        .normalize()
+}
+
+/// Build an orthonormal basis whose third axis is `normal`.
+fn orthonormal_basis(normal: Vec3) -> (Vec3, Vec3) {
+    let a = if normal.x.abs() > 0.9 {
+        Vec3::Y
+    } else {
+        Vec3::X
+    };
+    let tangent = normal.cross(a).normalize();
+    let bitangent = normal.cross(tangent);
+    (tangent, bitangent)
+}
+
+fn pixel_seed(x: u32, y: u32, pass: u32) -> u64 {
+    (x as u64)
+        .wrapping_mul(1973)
+        .wrapping_add((y as u64).wrapping_mul(9277))
+        .wrapping_add((pass as u64).wrapping_mul(26699))
+}
+
+/// Offset bounce origins off the surface to avoid self-intersection.
+const NUDGE: f32 = 1e-3;
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Output {
     pub width: u32,
     pub height: u32,
     pub escape: f32,
+    /// Integration step length for the gravitational ray march.
+    pub dt: f32,
+    /// Jittered samples per pixel for anti-aliasing.
+    pub samples: u32,
+    /// Number of accumulation passes for the path tracer.
+    pub passes: u32,
+    /// Maximum number of bounces before a path is terminated.
+    pub max_depth: u32,
+    /// Number of worker threads rendering bands of the frame in parallel.
+    pub threads: usize,
+    /// Destination for the color image; the path tracer flushes the running
+    /// average here after every pass so the image can be watched converging.
+    pub path: String,
 }
 
 impl Output {
@@ -0,0 +1,35 @@
+/// Small deterministic PRNG (PCG-XSH-RR 64/32) used for stochastic sampling.
+///
+/// A self-contained generator keeps sampling reproducible: each pass, pixel or
+/// tile can own an `Rng` seeded from its coordinates so results do not depend on
+/// evaluation order or thread scheduling.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Advance once so adjacent seeds do not produce correlated first draws.
+        let mut rng = Self {
+            state: seed.wrapping_add(0x9E37_79B9_7F4A_7C15),
+        };
+        rng.next_u32();
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
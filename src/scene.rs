@@ -1,6 +1,13 @@
 use serde::Deserialize;
 
-use crate::{light::Lights, material::Materials, object::Objects, ray::Camera};
+use crate::{
+    attract::Attractors,
+    light::Lights,
+    material::Materials,
+    object::{Inter, Objects},
+    ray::{Camera, Ray},
+    render::Output,
+};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Scene {
@@ -8,4 +15,36 @@ pub struct Scene {
     pub lights: Lights,
     pub materials: Materials,
     pub objects: Objects,
+    #[serde(default)]
+    pub attractors: Option<Attractors>,
+}
+
+impl Scene {
+    /// Propagate a primary ray until it hits geometry or escapes to the background.
+    ///
+    /// With no attractors the photon travels in a straight line and we fall back
+    /// to the analytic `Objects::intersect`. Otherwise the path is integrated as a
+    /// sequence of short geodesic segments: at each step the local gravitational
+    /// acceleration bends the velocity before we test the segment against the scene.
+    pub fn trace(&self, ray: &Ray, out: &Output) -> Option<Inter> {
+        let Some(attractors) = self.attractors.as_ref().filter(|a| !a.is_empty()) else {
+            return self.objects.intersect(ray, out.escape);
+        };
+
+        let mut pos = ray.pos;
+        let mut vel = ray.dir.normalize();
+        let mut length = 0.0;
+        while length < out.escape {
+            let a = attractors.attract(pos);
+            vel = (vel + a * out.dt).normalize();
+            let mut segment = Ray::new(pos, vel);
+            segment.time = ray.time;
+            if let Some(inter) = self.objects.intersect(&segment, out.dt) {
+                return Some(inter);
+            }
+            pos += vel * out.dt;
+            length += out.dt;
+        }
+        None
+    }
 }